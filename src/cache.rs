@@ -0,0 +1,219 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    fs,
+    path::Path,
+};
+
+use Stage;
+use ConvertedShader;
+use Reflection;
+use Resource;
+use Attribute;
+use converter::ConverterOptions;
+
+/// Computes a stable cache key covering everything that can affect the compiled output: the
+/// source text, the entry point, the stage, and every field of `ConverterOptions` except
+/// `cache_dir` and `bypass_cache`, which don't affect the compiled output.
+pub fn cache_key(source: &str,
+                 entry_point: &str,
+                 stage: Stage,
+                 options: &ConverterOptions) -> String {
+    let mut hasher = DefaultHasher::new();
+
+    source.hash(&mut hasher);
+    entry_point.hash(&mut hasher);
+    stage.hash(&mut hasher);
+    options.target_language.hash(&mut hasher);
+    format!("{:?}", options.target_version).hash(&mut hasher);
+
+    /* generate_debug_info in particular changes whether find_uniform_mappings reports real
+     member names or the memberN fallback, and optimization can eliminate dead
+     resources entirely, so both have to be part of the key alongside the target environment */
+    options.generate_debug_info.hash(&mut hasher);
+    format!("{:?}", options.optimization).hash(&mut hasher);
+    format!("{:?}", options.target_env).hash(&mut hasher);
+
+    let mut macro_names: Vec<_> = options.macros.keys().collect();
+    macro_names.sort();
+    for macro_name in macro_names {
+        macro_name.hash(&mut hasher);
+        options.macros[macro_name].hash(&mut hasher);
+    }
+
+    let mut include_paths: Vec<_> = options.include_search_paths.iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    include_paths.sort();
+    include_paths.hash(&mut hasher);
+
+    format!("{:016x}", hasher.finish())
+}
+
+/// Loads a previously-cached `ConvertedShader` for `key`, if present and well-formed.
+pub fn load(cache_dir: &Path, key: &str) -> Option<ConvertedShader> {
+    let bytes = fs::read(cache_dir.join(key)).ok()?;
+
+    decode(&bytes)
+}
+
+/// Stores `shader` under `key`. Failing to write the cache entry is non-fatal - the caller
+/// already has the freshly-compiled result to return.
+pub fn store(cache_dir: &Path, key: &str, shader: &ConvertedShader) {
+    if let Err(err) = store_inner(cache_dir, key, shader) {
+        warn!("failed to write shader cache entry `{}`: {}", key, err);
+    }
+}
+
+fn store_inner(cache_dir: &Path, key: &str, shader: &ConvertedShader) -> Result<(), String> {
+    fs::create_dir_all(cache_dir).map_err(|err| err.to_string())?;
+
+    fs::write(cache_dir.join(key), encode(shader)).map_err(|err| err.to_string())
+}
+
+fn encode(shader: &ConvertedShader) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    write_string(&mut bytes, &shader.shader);
+
+    write_u32(&mut bytes, shader.uniforms.len() as u32);
+    for (compiled_name, mapped_name) in &shader.uniforms {
+        write_string(&mut bytes, compiled_name);
+        write_string(&mut bytes, mapped_name);
+    }
+
+    write_resources(&mut bytes, &shader.reflection.uniform_buffers);
+    write_resources(&mut bytes, &shader.reflection.storage_buffers);
+    write_resources(&mut bytes, &shader.reflection.sampled_images);
+    write_resources(&mut bytes, &shader.reflection.separate_images);
+    write_resources(&mut bytes, &shader.reflection.separate_samplers);
+    write_resources(&mut bytes, &shader.reflection.push_constant_buffers);
+    write_attributes(&mut bytes, &shader.reflection.stage_inputs);
+    write_attributes(&mut bytes, &shader.reflection.stage_outputs);
+
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> Option<ConvertedShader> {
+    let mut cursor = 0;
+
+    let shader = read_string(bytes, &mut cursor)?;
+
+    let uniform_count = read_u32(bytes, &mut cursor)?;
+    let mut uniforms = HashMap::new();
+    for _ in 0..uniform_count {
+        let compiled_name = read_string(bytes, &mut cursor)?;
+        let mapped_name = read_string(bytes, &mut cursor)?;
+        uniforms.insert(compiled_name, mapped_name);
+    }
+
+    let reflection = Reflection {
+        uniform_buffers: read_resources(bytes, &mut cursor)?,
+        storage_buffers: read_resources(bytes, &mut cursor)?,
+        sampled_images: read_resources(bytes, &mut cursor)?,
+        separate_images: read_resources(bytes, &mut cursor)?,
+        separate_samplers: read_resources(bytes, &mut cursor)?,
+        push_constant_buffers: read_resources(bytes, &mut cursor)?,
+        stage_inputs: read_attributes(bytes, &mut cursor)?,
+        stage_outputs: read_attributes(bytes, &mut cursor)?,
+    };
+
+    Some(ConvertedShader { shader, uniforms, reflection })
+}
+
+fn write_resources(bytes: &mut Vec<u8>, resources: &[Resource]) {
+    write_u32(bytes, resources.len() as u32);
+    for resource in resources {
+        write_string(bytes, &resource.name);
+        write_u32(bytes, resource.id);
+        write_u32(bytes, resource.set);
+        write_u32(bytes, resource.binding);
+        write_string(bytes, &resource.base_type);
+
+        write_u32(bytes, resource.array.len() as u32);
+        for dim in &resource.array {
+            write_u32(bytes, *dim);
+        }
+    }
+}
+
+fn read_resources(bytes: &[u8], cursor: &mut usize) -> Option<Vec<Resource>> {
+    let count = read_u32(bytes, cursor)?;
+
+    let mut resources = Vec::new();
+    for _ in 0..count {
+        let name = read_string(bytes, cursor)?;
+        let id = read_u32(bytes, cursor)?;
+        let set = read_u32(bytes, cursor)?;
+        let binding = read_u32(bytes, cursor)?;
+        let base_type = read_string(bytes, cursor)?;
+
+        let array_len = read_u32(bytes, cursor)?;
+        let mut array = Vec::new();
+        for _ in 0..array_len {
+            array.push(read_u32(bytes, cursor)?);
+        }
+
+        resources.push(Resource { id, name, set, binding, base_type, array });
+    }
+
+    Some(resources)
+}
+
+fn write_attributes(bytes: &mut Vec<u8>, attributes: &[Attribute]) {
+    write_u32(bytes, attributes.len() as u32);
+    for attribute in attributes {
+        write_string(bytes, &attribute.name);
+        write_u32(bytes, attribute.id);
+        write_u32(bytes, attribute.location);
+    }
+}
+
+fn read_attributes(bytes: &[u8], cursor: &mut usize) -> Option<Vec<Attribute>> {
+    let count = read_u32(bytes, cursor)?;
+
+    let mut attributes = Vec::new();
+    for _ in 0..count {
+        let name = read_string(bytes, cursor)?;
+        let id = read_u32(bytes, cursor)?;
+        let location = read_u32(bytes, cursor)?;
+
+        attributes.push(Attribute { id, name, location });
+    }
+
+    Some(attributes)
+}
+
+fn write_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_string(bytes: &mut Vec<u8>, value: &str) {
+    write_u32(bytes, value.len() as u32);
+    bytes.extend_from_slice(value.as_bytes());
+}
+
+fn read_u32(bytes: &[u8], cursor: &mut usize) -> Option<u32> {
+    if bytes.len() < *cursor + 4 {
+        return None;
+    }
+
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(&bytes[*cursor..*cursor + 4]);
+    *cursor += 4;
+
+    Some(u32::from_le_bytes(buf))
+}
+
+fn read_string(bytes: &[u8], cursor: &mut usize) -> Option<String> {
+    let len = read_u32(bytes, cursor)? as usize;
+    if bytes.len() < *cursor + len {
+        return None;
+    }
+
+    let value = String::from_utf8(bytes[*cursor..*cursor + len].to_vec()).ok()?;
+    *cursor += len;
+
+    Some(value)
+}
@@ -1,5 +1,7 @@
 use spirv_cross::{
     glsl,
+    hlsl,
+    msl,
     spirv,
 };
 
@@ -11,12 +13,18 @@ use std::{
     fs::File,
     io::Read,
     collections::HashMap,
+    collections::HashSet,
 };
 
 use GlslVersion;
 use Stage;
+use TargetLanguage;
 use ConvertedShader;
+use Reflection;
+use Resource;
+use Attribute;
 use error::Error;
+use cache;
 
 #[derive(Debug, Clone)]
 pub struct ConverterOptions {
@@ -31,7 +39,32 @@ pub struct ConverterOptions {
     /// Macros to `#define` during compilation. Use `None` to define the macro without a value.
     pub macros: HashMap<String, Option<String>>,
 
+    /// The GLSL version to emit; this alone decides whether the output is desktop GLSL or GLSL ES.
+    /// Only affects the output for `TargetLanguage::GlslDesktop` and `TargetLanguage::GlslEs`.
     pub target_version: GlslVersion,
+
+    /// The shader dialect to emit from the compiled SPIR-V.
+    pub target_language: TargetLanguage,
+
+    /// Directory to use as a persistent on-disk cache of compiled shaders, keyed by a hash of
+    /// the source, entry point, stage and these options. `None` disables caching entirely.
+    pub cache_dir: Option<PathBuf>,
+
+    /// Skip reading from (but still write to) the cache in `cache_dir`, forcing a recompile.
+    pub bypass_cache: bool,
+
+    /// shaderc optimization level to compile with.
+    pub optimization: shaderc::OptimizationLevel,
+
+    /// Whether to emit debug info (including member debug names) into the compiled SPIR-V.
+    ///
+    /// `find_uniform_mappings` relies on member debug names to report property names, so
+    /// disabling this degrades those names to an index-based `member<N>` fallback (`N` being the
+    /// member's position in its struct) rather than failing.
+    pub generate_debug_info: bool,
+
+    /// The client API/version the SPIR-V is being compiled for, e.g. `(TargetEnv::Vulkan, 0)`.
+    pub target_env: (shaderc::TargetEnv, u32),
 }
 
 impl Default for ConverterOptions {
@@ -41,6 +74,14 @@ impl Default for ConverterOptions {
             macros: HashMap::new(),
 
             target_version: GlslVersion::V1_00Es,
+            target_language: TargetLanguage::GlslEs,
+
+            cache_dir: None,
+            bypass_cache: false,
+
+            optimization: shaderc::OptimizationLevel::Performance,
+            generate_debug_info: true,
+            target_env: (shaderc::TargetEnv::Vulkan, 0),
         }
     }
 }
@@ -49,7 +90,25 @@ impl ConverterOptions {
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+/// Resolves the content of an `#include` statement. Implement this to serve includes from
+/// somewhere other than the filesystem, e.g. from memory or an embedded asset bundle.
+pub trait IncludeResolver {
+    fn resolve_include(&self,
+                       name: &str,
+                       include_type: shaderc::IncludeType,
+                       from_path: &str,
+                       depth: usize) -> Result<shaderc::ResolvedInclude, String>;
+}
 
+/// The default `IncludeResolver`, which resolves `#include` statements against
+/// `ConverterOptions::include_search_paths` on disk. This is what `Converter::convert` uses.
+pub struct FilesystemIncludeResolver {
+    pub include_search_paths: Vec<PathBuf>,
+}
+
+impl IncludeResolver for FilesystemIncludeResolver {
     fn resolve_include(&self,
                        name: &str,
                        include_type: shaderc::IncludeType,
@@ -97,6 +156,10 @@ impl Converter {
 
     /// Convert a HLSL file to GLSL.
     ///
+    /// `#include` statements are resolved on disk, using `options.include_search_paths`. To
+    /// convert source that isn't on disk, or to resolve includes some other way, use
+    /// `convert_str` instead.
+    ///
     /// # Arguments
     ///
     /// * `source_path` - Location of HLSL source file.
@@ -112,34 +175,144 @@ impl Converter {
         where P: Into<PathBuf>
     {
         let source_path = source_path.into();
-        let source_filename = source_path.to_string_lossy();
+        let source_filename = source_path.to_string_lossy().into_owned();
 
         let mut source = String::new();
         File::open(&source_path)?.read_to_string(&mut source)?;
 
-        let spirv = self.hlsl_to_spirv(&source,
-                                       source_filename.as_ref(),
+        let include_resolver = FilesystemIncludeResolver {
+            include_search_paths: options.include_search_paths.clone(),
+        };
+
+        self.convert_str(&source, &source_filename, stage, entry_point, options, &include_resolver)
+    }
+
+    /// Convert HLSL source held in memory to the dialect selected by `options.target_language`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - HLSL source code.
+    /// * `virtual_filename` - Name to use for `source` in diagnostics; doesn't need to exist on disk.
+    /// * `stage` - Type of shader to create.
+    /// * `entry_point` - Name of function to use as entry point for this stage in the HLSL source.
+    /// * `options` - Converter configuration.
+    /// * `include_resolver` - Resolves the content of any `#include` statements in `source`.
+    pub fn convert_str(
+        &mut self,
+        source: &str,
+        virtual_filename: &str,
+        stage: Stage,
+        entry_point: &str,
+        options: &ConverterOptions,
+        include_resolver: &IncludeResolver) -> Result<ConvertedShader, Error>
+    {
+        let cache_key = options.cache_dir.as_ref().map(|_| {
+            let mut visited = HashSet::new();
+            visited.insert(virtual_filename.to_string());
+
+            let resolved_source = expand_includes_for_hash(source,
+                                                           virtual_filename,
+                                                           include_resolver,
+                                                           &mut visited);
+
+            cache::cache_key(&resolved_source, entry_point, stage, options)
+        });
+
+        if !options.bypass_cache {
+            if let (Some(cache_dir), Some(key)) = (options.cache_dir.as_ref(), cache_key.as_ref()) {
+                if let Some(cached) = cache::load(cache_dir, key) {
+                    return Ok(cached);
+                }
+            }
+        }
+
+        let spirv = self.hlsl_to_spirv(source,
+                                       virtual_filename,
                                        stage,
                                        entry_point,
-                                       options)?;
+                                       options,
+                                       include_resolver)?;
         let module = spirv::Module::from_words(&spirv);
 
-        let mut ast = spirv::Ast::<glsl::Target>::parse(&module)?;
-        spirv::Compile::set_compiler_options(&mut ast, &glsl::CompilerOptions {
-            version: options.target_version,
-            vertex: glsl::CompilerVertexOptions {
-                invert_y: false,
-                transform_clip_space: false,
-            },
-        })?;
+        let (shader, uniforms, reflection) = match options.target_language {
+            TargetLanguage::GlslDesktop | TargetLanguage::GlslEs => {
+                let mut ast = spirv::Ast::<glsl::Target>::parse(&module)?;
+                spirv::Compile::set_compiler_options(&mut ast, &glsl::CompilerOptions {
+                    version: options.target_version,
+                    vertex: glsl::CompilerVertexOptions {
+                        invert_y: false,
+                        transform_clip_space: false,
+                    },
+                })?;
+
+                (ast.compile()?,
+                 find_uniform_mappings(&ast, options.target_language)?,
+                 build_reflection(&ast, stage)?)
+            }
 
-        let shader = ast.compile()?;
-        let uniforms = find_uniform_mappings(&ast)?;
+            TargetLanguage::Msl => {
+                let mut ast = spirv::Ast::<msl::Target>::parse(&module)?;
+                spirv::Compile::set_compiler_options(&mut ast, &msl::CompilerOptions::default())?;
 
-        Ok(ConvertedShader {
+                (ast.compile()?,
+                 find_uniform_mappings(&ast, options.target_language)?,
+                 build_reflection(&ast, stage)?)
+            }
+
+            TargetLanguage::Hlsl => {
+                let mut ast = spirv::Ast::<hlsl::Target>::parse(&module)?;
+                spirv::Compile::set_compiler_options(&mut ast, &hlsl::CompilerOptions::default())?;
+
+                (ast.compile()?,
+                 find_uniform_mappings(&ast, options.target_language)?,
+                 build_reflection(&ast, stage)?)
+            }
+        };
+
+        let result = ConvertedShader {
             shader,
             uniforms,
-        })
+            reflection,
+        };
+
+        if let (Some(cache_dir), Some(key)) = (options.cache_dir.as_ref(), cache_key.as_ref()) {
+            cache::store(cache_dir, key, &result);
+        }
+
+        Ok(result)
+    }
+
+    /// Compile a named set of macro-define permutations of the same HLSL source in one call,
+    /// e.g. `shadows`, `shadows+normalmap`, `low_quality`.
+    ///
+    /// Each entry in `permutations` is a variant name paired with the macro defines for that
+    /// variant; `options.macros` is ignored and replaced per-permutation. If `options.cache_dir`
+    /// is set, unchanged permutations are served from the cache rather than recompiled.
+    pub fn convert_permutations(
+        &mut self,
+        source: &str,
+        virtual_filename: &str,
+        stage: Stage,
+        entry_point: &str,
+        options: &ConverterOptions,
+        include_resolver: &IncludeResolver,
+        permutations: &[(String, HashMap<String, Option<String>>)]) -> Result<HashMap<String, ConvertedShader>, Error>
+    {
+        let mut results = HashMap::new();
+
+        for &(ref name, ref macros) in permutations {
+            let permutation_options = ConverterOptions {
+                macros: macros.clone(),
+                ..options.clone()
+            };
+
+            let shader = self.convert_str(source, virtual_filename, stage, entry_point,
+                                          &permutation_options, include_resolver)?;
+
+            results.insert(name.clone(), shader);
+        }
+
+        Ok(results)
     }
 
     fn hlsl_to_spirv(&mut self,
@@ -147,14 +320,18 @@ impl Converter {
                      source_filename: &str,
                      stage: Stage,
                      entry_point: &str,
-                     options: &ConverterOptions) -> Result<Vec<u32>, Error> {
+                     options: &ConverterOptions,
+                     include_resolver: &IncludeResolver) -> Result<Vec<u32>, Error> {
         let mut opts = shaderc::CompileOptions::new().ok_or(Error::InitFailed)?;
         opts.set_source_language(shaderc::SourceLanguage::HLSL);
-        opts.set_target_env(shaderc::TargetEnv::Vulkan, 0);
-        opts.set_optimization_level(shaderc::OptimizationLevel::Performance);
-        opts.set_generate_debug_info();
+        let (target_env, target_env_version) = options.target_env;
+        opts.set_target_env(target_env, target_env_version);
+        opts.set_optimization_level(options.optimization);
+        if options.generate_debug_info {
+            opts.set_generate_debug_info();
+        }
         opts.set_include_callback(|name, include_type, from_path, depth| {
-            options.resolve_include(name, include_type, from_path, depth)
+            include_resolver.resolve_include(name, include_type, from_path, depth)
         });
 
         for (macro_name, macro_value) in options.macros.iter() {
@@ -164,6 +341,10 @@ impl Converter {
         let kind = match stage {
             Stage::Fragment => shaderc::ShaderKind::Fragment,
             Stage::Vertex => shaderc::ShaderKind::Vertex,
+            Stage::Compute => shaderc::ShaderKind::Compute,
+            Stage::Geometry => shaderc::ShaderKind::Geometry,
+            Stage::TessControl => shaderc::ShaderKind::TessControl,
+            Stage::TessEvaluation => shaderc::ShaderKind::TessEvaluation,
         };
 
         let artifact = self.compiler.compile_into_spirv(
@@ -181,16 +362,45 @@ impl Converter {
     }
 }
 
-fn find_uniform_mappings(ast: &spirv::Ast<glsl::Target>)
-                         -> Result<HashMap<String, String>, Error> {
+/// The prefix used for the flat `<prefix><id>.<member>` uniform buffer names differs per backend,
+/// since each `spirv_cross` target names the underlying constant-buffer variable differently.
+/// This only applies to `uniform_buffers`/`storage_buffers` - `spirv_cross` names combined image
+/// samplers the same way regardless of target, so sampled images always use `sampled_image_prefix`.
+fn uniform_name_prefix(target_language: TargetLanguage) -> &'static str {
+    match target_language {
+        TargetLanguage::Hlsl => "cb",
+        TargetLanguage::GlslDesktop | TargetLanguage::GlslEs | TargetLanguage::Msl => "_",
+    }
+}
+
+/// The prefix used for the flat `<prefix><id>` sampled-image name, which (unlike
+/// `uniform_name_prefix`) doesn't vary per backend.
+fn sampled_image_prefix() -> &'static str {
+    "_"
+}
+
+fn find_uniform_mappings<TTarget>(ast: &spirv::Ast<TTarget>, target_language: TargetLanguage)
+                         -> Result<HashMap<String, String>, Error>
+                         where TTarget: spirv::Target {
     let shader_resources = ast.get_shader_resources()?;
+    let prefix = uniform_name_prefix(target_language);
 
     let mut mappings = HashMap::new();
 
     /* discover property indices from debug names in the uniform buffers */
     for uniform_buffer in shader_resources.uniform_buffers {
         for member_name in get_member_names_deep(&ast, uniform_buffer.base_type_id)? {
-            let flat_name = format!("_{}.{}", uniform_buffer.id, member_name);
+            let flat_name = format!("{}{}.{}", prefix, uniform_buffer.id, member_name);
+
+            mappings.insert(flat_name, member_name);
+        }
+    }
+
+    /* storage buffers (SSBOs) only show up for compute-capable pipelines, but are discovered
+     the same way as uniform buffers */
+    for storage_buffer in shader_resources.storage_buffers {
+        for member_name in get_member_names_deep(&ast, storage_buffer.base_type_id)? {
+            let flat_name = format!("{}{}.{}", prefix, storage_buffer.id, member_name);
 
             mappings.insert(flat_name, member_name);
         }
@@ -198,21 +408,177 @@ fn find_uniform_mappings(ast: &spirv::Ast<glsl::Target>)
 
     /* samplers end up in sampled_images, separate_images and separate_samplers - final IDs
      are from sampled_images (the combined sampler resource), and names are from separate_images
-     (the Texture2D) */
-    for (image_index, sampled_image) in shader_resources.sampled_images.into_iter().enumerate() {
-        let image = &shader_resources.separate_images[image_index];
+     (the Texture2D). A texture sampled with more than one sampler state produces more combined
+     sampler variables than distinct textures, so the two lists can't be paired up by position -
+     resolve the underlying image id via get_combined_image_samplers() instead. */
+    let combined_samplers = ast.get_combined_image_samplers()?;
+
+    for sampled_image in shader_resources.sampled_images.iter() {
+        let compiled_name = format!("{}{}", sampled_image_prefix(), sampled_image.id);
+        let image_name = combined_image_name(&combined_samplers,
+                                             &shader_resources.separate_images,
+                                             sampled_image);
+
+        mappings.insert(compiled_name, image_name);
+    }
+
+    Ok(mappings)
+}
 
-        let compiled_name = format!("_{}", sampled_image.id);
+/// Resolves the original `Texture2D` name a combined image sampler was built from, via
+/// `get_combined_image_samplers()` rather than by assuming list position lines up between
+/// `sampled_images` and `separate_images`.
+fn combined_image_name(combined_samplers: &[spirv::CombinedImageSampler],
+                       separate_images: &[spirv::Resource],
+                       sampled_image: &spirv::Resource) -> String {
+    combined_samplers.iter()
+        .find(|combined| combined.combined_id == sampled_image.id)
+        .and_then(|combined| {
+            separate_images.iter().find(|image| image.id == combined.image_id)
+        })
+        .map(|image| image.name.to_string())
+        .unwrap_or_else(|| sampled_image.name.to_string())
+}
 
-        mappings.insert(compiled_name, image.name.to_string());
+/// Structured reflection over every resource category `spirv_cross` exposes, as an alternative
+/// to the flat names in `find_uniform_mappings`.
+fn build_reflection<TTarget>(ast: &spirv::Ast<TTarget>, stage: Stage)
+                            -> Result<Reflection, Error>
+                            where TTarget: spirv::Target {
+    let shader_resources = ast.get_shader_resources()?;
+
+    let mut reflection = Reflection::default();
+
+    reflection.uniform_buffers = describe_resources(ast, &shader_resources.uniform_buffers)?;
+    reflection.storage_buffers = describe_resources(ast, &shader_resources.storage_buffers)?;
+    let combined_samplers = ast.get_combined_image_samplers()?;
+    reflection.sampled_images = describe_sampled_images(ast,
+                                                        &combined_samplers,
+                                                        &shader_resources.sampled_images,
+                                                        &shader_resources.separate_images)?;
+    reflection.separate_images = describe_resources(ast, &shader_resources.separate_images)?;
+    reflection.separate_samplers = describe_resources(ast, &shader_resources.separate_samplers)?;
+    reflection.push_constant_buffers = describe_resources(ast, &shader_resources.push_constant_buffers)?;
+
+    if stage == Stage::Vertex {
+        reflection.stage_inputs = describe_attributes(ast, &shader_resources.stage_inputs)?;
+        reflection.stage_outputs = describe_attributes(ast, &shader_resources.stage_outputs)?;
     }
 
-    Ok(mappings)
+    Ok(reflection)
 }
 
-fn get_member_names_deep(ast: &spirv::Ast<glsl::Target>,
+fn describe_resources<TTarget>(ast: &spirv::Ast<TTarget>, resources: &[spirv::Resource])
+                               -> Result<Vec<Resource>, Error>
+                               where TTarget: spirv::Target {
+    resources.iter()
+        .map(|resource| {
+            let set = ast.get_decoration(resource.id, spirv::Decoration::DescriptorSet)?;
+            let binding = ast.get_decoration(resource.id, spirv::Decoration::Binding)?;
+            let (base_type, array) = describe_type(ast, resource.base_type_id)?;
+
+            Ok(Resource {
+                id: resource.id,
+                name: resource.name.to_string(),
+                set,
+                binding,
+                base_type,
+                array,
+            })
+        })
+        .collect()
+}
+
+/// Like `describe_resources`, but for `sampled_images` specifically - the combined sampler
+/// resource doesn't carry the original name, so (as in `find_uniform_mappings`) it's resolved
+/// via `get_combined_image_samplers()` against the corresponding `separate_images` entry.
+fn describe_sampled_images<TTarget>(ast: &spirv::Ast<TTarget>,
+                                    combined_samplers: &[spirv::CombinedImageSampler],
+                                    sampled_images: &[spirv::Resource],
+                                    separate_images: &[spirv::Resource])
+                                    -> Result<Vec<Resource>, Error>
+                                    where TTarget: spirv::Target {
+    sampled_images.iter()
+        .map(|sampled_image| {
+            let set = ast.get_decoration(sampled_image.id, spirv::Decoration::DescriptorSet)?;
+            let binding = ast.get_decoration(sampled_image.id, spirv::Decoration::Binding)?;
+            let (base_type, array) = describe_type(ast, sampled_image.base_type_id)?;
+            let name = combined_image_name(combined_samplers, separate_images, sampled_image);
+
+            Ok(Resource {
+                id: sampled_image.id,
+                name,
+                set,
+                binding,
+                base_type,
+                array,
+            })
+        })
+        .collect()
+}
+
+fn describe_attributes<TTarget>(ast: &spirv::Ast<TTarget>, resources: &[spirv::Resource])
+                                -> Result<Vec<Attribute>, Error>
+                                where TTarget: spirv::Target {
+    resources.iter()
+        .map(|resource| {
+            let location = ast.get_decoration(resource.id, spirv::Decoration::Location)?;
+
+            Ok(Attribute {
+                id: resource.id,
+                name: resource.name.to_string(),
+                location,
+            })
+        })
+        .collect()
+}
+
+fn describe_type<TTarget>(ast: &spirv::Ast<TTarget>, type_id: u32)
+                          -> Result<(String, Vec<u32>), Error>
+                          where TTarget: spirv::Target {
+    let ty = ast.get_type(type_id)?;
+
+    let array = match &ty {
+        &spirv::Type::Struct { ref array, .. } |
+        &spirv::Type::Float { ref array } |
+        &spirv::Type::Double { ref array } |
+        &spirv::Type::Int { ref array } |
+        &spirv::Type::Int64 { ref array } |
+        &spirv::Type::UInt { ref array } |
+        &spirv::Type::UInt64 { ref array } |
+        &spirv::Type::Boolean { ref array } |
+        &spirv::Type::Char { ref array } |
+        &spirv::Type::Half { ref array } => array.clone(),
+
+        _ => Vec::new(),
+    };
+
+    let base_type = match ty {
+        spirv::Type::Struct { .. } => "struct",
+        spirv::Type::Float { .. } => "float",
+        spirv::Type::Double { .. } => "double",
+        spirv::Type::Int { .. } => "int",
+        spirv::Type::Int64 { .. } => "int64",
+        spirv::Type::UInt { .. } => "uint",
+        spirv::Type::UInt64 { .. } => "uint64",
+        spirv::Type::Boolean { .. } => "bool",
+        spirv::Type::Char { .. } => "char",
+        spirv::Type::Half { .. } => "half",
+        spirv::Type::Image { .. } => "image",
+        spirv::Type::SampledImage { .. } => "sampled_image",
+        spirv::Type::Sampler { .. } => "sampler",
+        spirv::Type::AtomicCounter { .. } => "atomic_counter",
+        spirv::Type::Void => "void",
+        spirv::Type::Unknown => "unknown",
+    };
+
+    Ok((base_type.to_string(), array))
+}
+
+fn get_member_names_deep<TTarget>(ast: &spirv::Ast<TTarget>,
                          struct_type_id: u32)
-                         -> Result<Vec<String>, Error> {
+                         -> Result<Vec<String>, Error>
+                         where TTarget: spirv::Target {
     let (member_types, _member_array_sizes) = match ast.get_type(struct_type_id)? {
         spirv::Type::Struct { member_types, array } => (member_types, array),
         _ => panic!("uniform buffer must be a struct"),
@@ -224,6 +590,14 @@ fn get_member_names_deep(ast: &spirv::Ast<glsl::Target>,
 
         let member_base_name = ast.get_member_name(struct_type_id, member_id)?;
 
+        /* without debug info there are no member names to report - fall back to a
+         name indexed by the member's position in the struct rather than failing */
+        let member_base_name = if member_base_name.is_empty() {
+            format!("member{}", member_id)
+        } else {
+            member_base_name
+        };
+
         match ast.get_type(member_type)? {
             spirv::Type::Struct { ref array, .. } => {
                 let element_names = array_member_names(&member_base_name, array);
@@ -290,6 +664,72 @@ fn array_member_names(base_name: &str, array_dims: &[u32]) -> Vec<String> {
     array_element_names
 }
 
+/// Expands `#include` statements in `source` using `include_resolver`, for cache hashing purposes
+/// only - the actual compile still resolves includes itself, via `hlsl_to_spirv`'s include
+/// callback. This lets `cache_key` notice when an included file changes even though the
+/// top-level source and `ConverterOptions` didn't.
+///
+/// `visited` tracks every `resolved_name` expanded so far (it is never pruned, so it covers the
+/// whole expansion, not just the current include path), and each header is expanded at most once.
+/// Unlike the real shaderc/glslang preprocessor, this hand-rolled expander doesn't understand
+/// `#pragma once`/include guards, so two headers that include each other would otherwise
+/// recurse forever.
+fn expand_includes_for_hash(source: &str,
+                            from_path: &str,
+                            include_resolver: &IncludeResolver,
+                            visited: &mut HashSet<String>) -> String {
+    let mut expanded = String::with_capacity(source.len());
+
+    for line in source.lines() {
+        match parse_include_directive(line) {
+            Some((name, include_type)) => {
+                match include_resolver.resolve_include(name, include_type, from_path, visited.len()) {
+                    Ok(resolved) => {
+                        if visited.insert(resolved.resolved_name.clone()) {
+                            let included = expand_includes_for_hash(&resolved.content,
+                                                                    &resolved.resolved_name,
+                                                                    include_resolver,
+                                                                    visited);
+                            expanded.push_str(&included);
+                        }
+
+                        /* already expanded this header earlier - skip re-expanding it, same
+                         as an include guard would, rather than recursing forever */
+                    }
+
+                    /* if the include can't be resolved here, the directive itself still ends up
+                     part of the hashed text, so a header that starts/stops existing still
+                     changes the key */
+                    Err(_) => expanded.push_str(line),
+                }
+            }
+
+            None => expanded.push_str(line),
+        }
+
+        expanded.push('\n');
+    }
+
+    expanded
+}
+
+fn parse_include_directive(line: &str) -> Option<(&str, shaderc::IncludeType)> {
+    let rest = line.trim_start();
+    if !rest.starts_with("#include") {
+        return None;
+    }
+
+    let rest = rest["#include".len()..].trim();
+
+    if rest.len() >= 2 && rest.starts_with('"') && rest.ends_with('"') {
+        Some((&rest[1..rest.len() - 1], shaderc::IncludeType::Relative))
+    } else if rest.len() >= 2 && rest.starts_with('<') && rest.ends_with('>') {
+        Some((&rest[1..rest.len() - 1], shaderc::IncludeType::Standard))
+    } else {
+        None
+    }
+}
+
 fn find_source_file<P>(name: &str, source_paths: &[P]) -> Result<PathBuf, String>
     where P: AsRef<Path>
 {
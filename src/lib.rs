@@ -7,6 +7,8 @@ extern crate log;
 pub mod error;
 pub mod converter;
 
+mod cache;
+
 #[cfg(test)]
 extern crate tempfile;
 
@@ -18,7 +20,7 @@ mod test;
 
 pub use self::{
     error::Error,
-    converter::{ Converter, ConverterOptions, }
+    converter::{ Converter, ConverterOptions, IncludeResolver, FilesystemIncludeResolver, }
 };
 
 pub use self::spirv_cross::glsl::Version as GlslVersion;
@@ -31,6 +33,29 @@ use std::{
 pub enum Stage {
     Fragment,
     Vertex,
+    Compute,
+    Geometry,
+    TessControl,
+    TessEvaluation,
+}
+
+/// The shader dialect to emit from the SPIR-V produced by the HLSL compile step.
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
+pub enum TargetLanguage {
+    /// GLSL. Emits the same output as `GlslEs`: whether that is desktop GLSL (e.g. `#version 450`)
+    /// or GLSL ES is decided by `ConverterOptions::target_version`, not by this variant.
+    GlslDesktop,
+
+    /// GLSL. Emits the same output as `GlslDesktop`: whether that is GLSL ES (e.g.
+    /// `#version 300 es`) or desktop GLSL is decided by `ConverterOptions::target_version`, not by
+    /// this variant.
+    GlslEs,
+
+    /// Metal Shading Language.
+    Msl,
+
+    /// HLSL.
+    Hlsl,
 }
 
 #[derive(Clone, Debug)]
@@ -41,5 +66,61 @@ pub struct ConvertedShader {
     /// Compiled uniform names, mapped to variable names.
     /// May be missing uniforms that were removed as unused.
     pub uniforms: HashMap<String, String>,
+
+    /// Structured SPIR-V reflection data for this shader's resources.
+    pub reflection: Reflection,
+}
+
+/// A single resource discovered via reflection - a UBO, SSBO, sampler etc.
+#[derive(Clone, Debug)]
+pub struct Resource {
+    /// The SPIR-V id of the resource variable.
+    pub id: u32,
+
+    /// The resolved GLSL variable name.
+    pub name: String,
+
+    /// `layout(set = ...)`.
+    pub set: u32,
+
+    /// `layout(binding = ...)`.
+    pub binding: u32,
+
+    /// The resource's base type, e.g. `struct`, `float`, `sampled_image`.
+    pub base_type: String,
+
+    /// Array dimensions, outermost first. Empty if the resource isn't an array.
+    pub array: Vec<u32>,
+}
+
+/// A single vertex stage input or output attribute discovered via reflection.
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    /// The SPIR-V id of the attribute variable.
+    pub id: u32,
+
+    /// The resolved GLSL variable name.
+    pub name: String,
+
+    /// `layout(location = ...)`.
+    pub location: u32,
+}
+
+/// Structured SPIR-V reflection data for a shader's resources, as an alternative to the flat
+/// `_id.member` names in `ConvertedShader::uniforms`.
+#[derive(Clone, Debug, Default)]
+pub struct Reflection {
+    pub uniform_buffers: Vec<Resource>,
+    pub storage_buffers: Vec<Resource>,
+    pub sampled_images: Vec<Resource>,
+    pub separate_images: Vec<Resource>,
+    pub separate_samplers: Vec<Resource>,
+    pub push_constant_buffers: Vec<Resource>,
+
+    /// Only populated when reflecting a `Stage::Vertex` shader.
+    pub stage_inputs: Vec<Attribute>,
+
+    /// Only populated when reflecting a `Stage::Vertex` shader.
+    pub stage_outputs: Vec<Attribute>,
 }
 
@@ -4,9 +4,20 @@ use std::{
 };
 use tempfile;
 use regex::*;
+use shaderc;
 use super::*;
 
 fn compile_test_shader(stage: Stage, entry_point: &str, src: &str) -> ConvertedShader {
+    compile_test_shader_with_options(stage, entry_point, src, ConverterOptions {
+        target_version: GlslVersion::V1_50,
+        ..ConverterOptions::default()
+    })
+}
+
+fn compile_test_shader_with_options(stage: Stage,
+                                    entry_point: &str,
+                                    src: &str,
+                                    opts: ConverterOptions) -> ConvertedShader {
     let tmp_dir = tempfile::tempdir()
         .expect("faild to create temp dir for shader")
         .into_path();
@@ -22,11 +33,6 @@ fn compile_test_shader(stage: Stage, entry_point: &str, src: &str) -> ConvertedS
         let mut converter = Converter::new()
             .expect("converter init failed");
 
-        let opts = ConverterOptions {
-            target_version: GlslVersion::V1_50,
-            ..ConverterOptions::default()
-        };
-
         converter.convert(&tmp_path, stage, entry_point, &opts)
             .expect("compilation failed")
     };
@@ -161,3 +167,324 @@ fn ubo_array_of_struct_has_prop_mapping() {
     assert_member_matches(&ubo_members, "a[0].b");
     assert_member_matches(&ubo_members, "a[1].b");
 }
+
+#[test]
+fn reflection_uniform_buffer_is_discovered() {
+    let shader = compile_test_shader(Stage::Vertex, "vertex", r"
+        [[vk::binding(2, 1)]]
+        struct A { float b; } a;
+
+        float4 vertex(): SV_POSITION { return float4(0.0, 0.0, 0.0, a.b); }
+    ");
+
+    assert_eq!(1, shader.reflection.uniform_buffers.len());
+    assert_eq!("struct", shader.reflection.uniform_buffers[0].base_type);
+    assert_eq!(1, shader.reflection.uniform_buffers[0].set);
+    assert_eq!(2, shader.reflection.uniform_buffers[0].binding);
+}
+
+#[test]
+fn reflection_sampled_image_takes_name_from_separate_image() {
+    let shader = compile_test_shader(Stage::Fragment, "fragment", r"
+        [[vk::binding(2, 1)]]
+        Texture2D tex;
+        SamplerState samp;
+
+        float4 fragment(): SV_TARGET { return tex.Sample(samp, float2(0.0, 0.0)); }
+    ");
+
+    assert_eq!(1, shader.reflection.sampled_images.len());
+    assert_eq!(1, shader.reflection.separate_images.len());
+    assert_eq!(1, shader.reflection.separate_samplers.len());
+
+    assert_eq!("tex", shader.reflection.sampled_images[0].name);
+    assert_eq!("tex", shader.reflection.separate_images[0].name);
+
+    assert_eq!(1, shader.reflection.sampled_images[0].set);
+    assert_eq!(2, shader.reflection.sampled_images[0].binding);
+    assert!(shader.reflection.sampled_images[0].array.is_empty());
+}
+
+#[test]
+fn reflection_vertex_input_location_is_discovered() {
+    let shader = compile_test_shader(Stage::Vertex, "vertex", r"
+        float4 vertex(float4 position: POSITION): SV_POSITION { return position; }
+    ");
+
+    assert_eq!(1, shader.reflection.stage_inputs.len());
+    assert_eq!("position", shader.reflection.stage_inputs[0].name);
+    assert_eq!(0, shader.reflection.stage_inputs[0].location);
+}
+
+#[test]
+fn reflection_storage_buffer_is_discovered_for_compute() {
+    let shader = compile_test_shader_with_options(Stage::Compute, "main", r"
+        RWStructuredBuffer<float> data;
+
+        [numthreads(1, 1, 1)]
+        void main() { data[0] = 1.0; }
+    ", ConverterOptions {
+        target_version: GlslVersion::V4_30,
+        ..ConverterOptions::default()
+    });
+
+    assert_eq!(1, shader.reflection.storage_buffers.len());
+}
+
+#[test]
+fn cache_round_trip_returns_same_shader() {
+    let cache_dir = tempfile::tempdir()
+        .expect("failed to create temp dir for cache")
+        .into_path();
+
+    let src = r"
+        struct A { float b; } a;
+
+        float4 vertex(): SV_POSITION { return float4(0.0, 0.0, 0.0, a.b); }
+    ";
+
+    let opts = ConverterOptions {
+        target_version: GlslVersion::V1_50,
+        cache_dir: Some(cache_dir.clone()),
+        ..ConverterOptions::default()
+    };
+
+    let compiled = compile_test_shader_with_options(Stage::Vertex, "vertex", src, opts.clone());
+
+    assert!(fs::read_dir(&cache_dir).expect("cache dir should exist").count() > 0);
+
+    let cached = compile_test_shader_with_options(Stage::Vertex, "vertex", src, opts);
+
+    assert_eq!(compiled.shader, cached.shader);
+    assert_eq!(compiled.uniforms, cached.uniforms);
+}
+
+#[test]
+fn reflection_push_constant_block_is_discovered() {
+    let shader = compile_test_shader(Stage::Fragment, "fragment", r"
+        [[vk::push_constant]]
+        struct { float a; } push;
+
+        float4 fragment(): SV_TARGET { return float4(push.a, 0.0, 0.0, 0.0); }
+    ");
+
+    assert_eq!(1, shader.reflection.push_constant_buffers.len());
+}
+
+#[test]
+fn geometry_stage_compiles() {
+    let shader = compile_test_shader_with_options(Stage::Geometry, "main", r"
+        struct GS_OUTPUT { float4 pos: SV_POSITION; };
+
+        [maxvertexcount(3)]
+        void main(triangle float4 input[3]: SV_POSITION, inout TriangleStream<GS_OUTPUT> output) {
+            for (int i = 0; i < 3; i++) {
+                GS_OUTPUT o;
+                o.pos = input[i];
+                output.Append(o);
+            }
+        }
+    ", ConverterOptions {
+        target_version: GlslVersion::V4_30,
+        ..ConverterOptions::default()
+    });
+
+    assert!(!shader.shader.is_empty());
+}
+
+#[test]
+fn tess_control_stage_compiles() {
+    let shader = compile_test_shader_with_options(Stage::TessControl, "main", r#"
+        struct HS_INPUT { float4 pos: SV_POSITION; };
+        struct HS_OUTPUT { float4 pos: SV_POSITION; };
+        struct HS_CONSTANT_OUTPUT {
+            float edges[3]: SV_TessFactor;
+            float inside: SV_InsideTessFactor;
+        };
+
+        HS_CONSTANT_OUTPUT PatchConstantFunc(InputPatch<HS_INPUT, 3> patch) {
+            HS_CONSTANT_OUTPUT output;
+            output.edges[0] = 1.0;
+            output.edges[1] = 1.0;
+            output.edges[2] = 1.0;
+            output.inside = 1.0;
+            return output;
+        }
+
+        [domain("tri")]
+        [partitioning("integer")]
+        [outputtopology("triangle_cw")]
+        [outputcontrolpoints(3)]
+        [patchconstantfunc("PatchConstantFunc")]
+        HS_OUTPUT main(InputPatch<HS_INPUT, 3> patch, uint id: SV_OutputControlPointID) {
+            HS_OUTPUT output;
+            output.pos = patch[id].pos;
+            return output;
+        }
+    "#, ConverterOptions {
+        target_version: GlslVersion::V4_30,
+        ..ConverterOptions::default()
+    });
+
+    assert!(!shader.shader.is_empty());
+}
+
+#[test]
+fn tess_evaluation_stage_compiles() {
+    let shader = compile_test_shader_with_options(Stage::TessEvaluation, "main", r#"
+        struct DS_OUTPUT { float4 pos: SV_POSITION; };
+        struct HS_OUTPUT { float4 pos: SV_POSITION; };
+        struct HS_CONSTANT_OUTPUT {
+            float edges[3]: SV_TessFactor;
+            float inside: SV_InsideTessFactor;
+        };
+
+        [domain("tri")]
+        DS_OUTPUT main(HS_CONSTANT_OUTPUT constant_data,
+                       float3 uvw: SV_DomainLocation,
+                       const OutputPatch<HS_OUTPUT, 3> patch) {
+            DS_OUTPUT output;
+            output.pos = patch[0].pos * uvw.x + patch[1].pos * uvw.y + patch[2].pos * uvw.z;
+            return output;
+        }
+    "#, ConverterOptions {
+        target_version: GlslVersion::V4_30,
+        ..ConverterOptions::default()
+    });
+
+    assert!(!shader.shader.is_empty());
+}
+
+#[test]
+fn msl_target_compiles() {
+    let shader = compile_test_shader_with_options(Stage::Vertex, "vertex", r"
+        struct A { float b; } a;
+
+        float4 vertex(): SV_POSITION { return float4(0.0, 0.0, 0.0, a.b); }
+    ", ConverterOptions {
+        target_language: TargetLanguage::Msl,
+        ..ConverterOptions::default()
+    });
+
+    assert!(shader.shader.contains("using namespace metal"));
+
+    let ubo_members = get_ubo_member_mappings(&shader);
+    assert_eq!(1, ubo_members.len());
+    assert_member_matches(&ubo_members, "a.b");
+}
+
+#[test]
+fn hlsl_target_compiles_and_does_not_prefix_sampled_image_names_with_cb() {
+    let shader = compile_test_shader_with_options(Stage::Fragment, "fragment", r"
+        Texture2D tex;
+        SamplerState samp;
+
+        float4 fragment(): SV_TARGET { return tex.Sample(samp, float2(0.0, 0.0)); }
+    ", ConverterOptions {
+        target_language: TargetLanguage::Hlsl,
+        ..ConverterOptions::default()
+    });
+
+    let (compiled_name, mapped_name) = shader.uniforms.iter().next()
+        .expect("sampled image should have a uniform mapping");
+
+    assert!(!compiled_name.starts_with("cb"));
+    assert_eq!("tex", mapped_name);
+}
+
+#[test]
+fn convert_str_resolves_includes_via_custom_resolver() {
+    struct InMemoryIncludeResolver;
+
+    impl IncludeResolver for InMemoryIncludeResolver {
+        fn resolve_include(&self,
+                           name: &str,
+                           _include_type: shaderc::IncludeType,
+                           _from_path: &str,
+                           _depth: usize) -> Result<shaderc::ResolvedInclude, String> {
+            if name == "common.hlsl" {
+                Ok(shaderc::ResolvedInclude {
+                    resolved_name: name.to_string(),
+                    content: "struct A { float b; } a;".to_string(),
+                })
+            } else {
+                Err(format!("unknown include `{}`", name))
+            }
+        }
+    }
+
+    let mut converter = Converter::new()
+        .expect("converter init failed");
+
+    let shader = converter.convert_str(r#"
+        #include "common.hlsl"
+
+        float4 vertex(): SV_POSITION { return float4(0.0, 0.0, 0.0, a.b); }
+    "#, "vertex.hlsl", Stage::Vertex, "vertex", &ConverterOptions {
+        target_version: GlslVersion::V1_50,
+        ..ConverterOptions::default()
+    }, &InMemoryIncludeResolver)
+        .expect("compilation failed");
+
+    let ubo_members = get_ubo_member_mappings(&shader);
+    assert_member_matches(&ubo_members, "a.b");
+}
+
+#[test]
+fn reflection_falls_back_to_indexed_member_names_without_debug_info() {
+    let shader = compile_test_shader_with_options(Stage::Vertex, "vertex", r"
+        struct A { float b; } a;
+
+        float4 vertex(): SV_POSITION { return float4(0.0, 0.0, 0.0, a.b); }
+    ", ConverterOptions {
+        target_version: GlslVersion::V1_50,
+        generate_debug_info: false,
+        ..ConverterOptions::default()
+    });
+
+    let ubo_members = get_ubo_member_mappings(&shader);
+
+    assert_eq!(1, ubo_members.len());
+    assert_member_matches(&ubo_members, "member0");
+}
+
+#[test]
+fn convert_permutations_compiles_each_macro_set() {
+    let mut converter = Converter::new()
+        .expect("converter init failed");
+
+    let src = r"
+        float4 vertex(): SV_POSITION {
+        #ifdef USE_OFFSET
+            return float4(1.0, 0.0, 0.0, 1.0);
+        #else
+            return float4(0.0, 0.0, 0.0, 1.0);
+        #endif
+        }
+    ";
+
+    let include_resolver = FilesystemIncludeResolver {
+        include_search_paths: Vec::new(),
+    };
+
+    let mut with_offset = HashMap::new();
+    with_offset.insert("USE_OFFSET".to_string(), None);
+
+    let permutations = vec![
+        ("default".to_string(), HashMap::new()),
+        ("with_offset".to_string(), with_offset),
+    ];
+
+    let results = converter.convert_permutations(src, "vertex.hlsl", Stage::Vertex, "vertex",
+                                                 &ConverterOptions {
+                                                     target_version: GlslVersion::V1_50,
+                                                     ..ConverterOptions::default()
+                                                 },
+                                                 &include_resolver,
+                                                 &permutations)
+        .expect("compilation failed");
+
+    assert_eq!(2, results.len());
+    assert!(results.contains_key("default"));
+    assert!(results.contains_key("with_offset"));
+}